@@ -0,0 +1,261 @@
+//! Async multilock support, modeled on the poll-based lock futures used by
+//! `futures-util`'s `Mutex`/`BiLock`.
+//!
+//! Locks are still acquired in ascending address order, but because there
+//! is no blocking primitive to wait on, [`AsyncBuilder::finish`] returns a
+//! future which polls each lock's acquire future to completion before
+//! starting the next. Acquisition order is therefore always the same fixed
+//! global order regardless of how the returned future is polled, so no
+//! interleaving between concurrently-polled futures can deadlock.
+//!
+//! Unlike the synchronous [`Builder`](crate::Builder), `AsyncBuilder` is
+//! constructed directly with [`AsyncBuilder::new`] rather than through a
+//! callback passed to a `multilock`-style free function, and its types are
+//! not branded with an invariant `'id` lifetime. A callback-based entry
+//! point would need to return a future that names the brand, and that
+//! future cannot then be driven to completion outside of the callback's own
+//! `for<'id>` scope. Each [`AsyncToken`] instead directly borrows its own
+//! `AsyncMutex`, so `get`/`get_mut` always operate on the right data
+//! regardless of which `AsyncLocker` is passed in; since nothing ties an
+//! `AsyncToken` to a particular `AsyncLocker` at compile time, those two
+//! methods are `unsafe` and callers are responsible for only calling them
+//! once the corresponding lock is actually held. They do still carry a
+//! `debug_assert!` against [`RawMutexAsync::is_locked`], the same backstop
+//! the synchronous `Token`/`MappedToken` use, which catches the obvious
+//! misuse (an unrelated or not-yet-resolved `AsyncLocker`) in debug builds.
+
+use core::cell::UnsafeCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use smallvec::SmallVec;
+
+/// A raw mutex type that supports asynchronous, poll-based acquisition.
+///
+/// This is the `async` analogue of [`lock_api::RawMutex`], implemented by
+/// async-aware raw mutex primitives rather than by types backed by a
+/// blocking OS primitive.
+pub trait RawMutexAsync {
+    /// Initial, unlocked value of this type.
+    const INIT: Self;
+
+    /// Future returned by [`RawMutexAsync::lock`].
+    type LockFuture<'a>: Future<Output = ()>
+    where
+        Self: 'a;
+
+    /// Acquire this mutex, returning a future that resolves once it is held.
+    fn lock(&self) -> Self::LockFuture<'_>;
+
+    /// Release a previously-acquired lock.
+    ///
+    /// # Safety
+    /// May only be called once for each successful acquisition via `lock`.
+    unsafe fn unlock(&self);
+
+    /// Check whether this mutex is currently locked.
+    ///
+    /// Used only for `debug_assert!`s backing up `AsyncToken::get`/`get_mut`,
+    /// since unlike the synchronous `Token`, nothing ties an `AsyncToken` to
+    /// a particular `AsyncLocker` at compile time.
+    fn is_locked(&self) -> bool;
+}
+
+/// A mutex usable with [`multilock_async`].
+///
+/// Unlike [`lock_api::Mutex`], the raw lock type must support asynchronous,
+/// poll-based acquisition via [`RawMutexAsync`] rather than blocking.
+pub struct AsyncMutex<R, T> {
+    raw: R,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<R: Send, T: Send> Send for AsyncMutex<R, T> {}
+unsafe impl<R: Sync, T: Send> Sync for AsyncMutex<R, T> {}
+
+impl<R: RawMutexAsync, T> AsyncMutex<R, T> {
+    /// Create a new, unlocked `AsyncMutex` wrapping `val`.
+    pub const fn new(val: T) -> Self {
+        AsyncMutex {
+            raw: R::INIT,
+            data: UnsafeCell::new(val),
+        }
+    }
+}
+
+impl<R, T> AsyncMutex<R, T> {
+    /// Get a reference to the raw lock, to lock and unlock it directly.
+    ///
+    /// # Safety
+    /// The caller is responsible for matching every `lock` with an
+    /// `unlock`, and for not accessing `data_ptr` without the lock held.
+    unsafe fn raw(&self) -> &R {
+        &self.raw
+    }
+
+    /// Get a raw pointer to the underlying data, bypassing any locking.
+    fn data_ptr(&self) -> *mut T {
+        self.data.get()
+    }
+}
+
+impl<R: RawMutexAsync, T> AsyncMutex<R, T> {
+    /// Check whether this mutex is currently locked.
+    fn is_locked(&self) -> bool {
+        self.raw.is_locked()
+    }
+}
+
+/// Reference a mutex which was registered with an `AsyncLocker`.
+///
+/// When combined with an `AsyncLocker`, may be used to access the locked
+/// data.
+pub struct AsyncToken<'a, R, T> {
+    mutex: &'a AsyncMutex<R, T>,
+}
+
+impl<'a, R: RawMutexAsync, T> AsyncToken<'a, R, T> {
+    /// Get a shared reference to the value locked with this token.
+    ///
+    /// # Safety
+    /// `locker` must actually hold this token's mutex. Unlike the
+    /// synchronous `Token`, this isn't enforced at compile time: nothing
+    /// stops a caller from passing in an `AsyncLocker` that never locked
+    /// this mutex at all, so the precondition is on the caller instead.
+    pub unsafe fn get<'b>(&'b self, _locker: &'b AsyncLocker<'a, R>) -> &'b T {
+        debug_assert!(self.mutex.is_locked());
+        // safety: see this method's own safety section.
+        unsafe { &*self.mutex.data_ptr() }
+    }
+
+    /// Get a mutable reference to the value locked with this token.
+    ///
+    /// # Safety
+    /// See `get` above: `locker` must actually hold this token's mutex.
+    pub unsafe fn get_mut<'b>(&'b mut self, _locker: &'b AsyncLocker<'a, R>) -> &'b mut T {
+        debug_assert!(self.mutex.is_locked());
+        // safety: see `get` above.
+        unsafe { &mut *self.mutex.data_ptr() }
+    }
+}
+
+/// Builder type used to register `AsyncMutex` references to be locked.
+pub struct AsyncBuilder<'a, R> {
+    locks: SmallVec<[&'a R; 4]>,
+}
+
+impl<'a, R: RawMutexAsync> Default for AsyncBuilder<'a, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, R: RawMutexAsync> AsyncBuilder<'a, R> {
+    /// Create a new, empty `AsyncBuilder`.
+    pub fn new() -> Self {
+        AsyncBuilder {
+            locks: SmallVec::new(),
+        }
+    }
+
+    /// Register a new mutex to be locked by this `AsyncBuilder`.
+    pub fn add<T>(&mut self, mutex: &'a AsyncMutex<R, T>) -> AsyncToken<'a, R, T> {
+        // Safety: Acquiring a reference to lock and unlock the underlying
+        // raw mutex in other methods.
+        unsafe {
+            self.locks.push(mutex.raw());
+        }
+        AsyncToken { mutex }
+    }
+
+    /// Lock all mutexes registered with this builder, in ascending address
+    /// order, returning a future resolving to an `AsyncLocker` once every
+    /// lock is held.
+    pub fn finish(self) -> Finish<'a, R> {
+        // Acquire each lock in ascending address order, as in the
+        // synchronous `Builder::finish`.
+        let mut locks = self.locks;
+        locks.sort_unstable_by_key(|r| *r as *const R as usize);
+        Finish {
+            locks,
+            index: 0,
+            current: None,
+        }
+    }
+}
+
+/// Future returned by [`AsyncBuilder::finish`].
+///
+/// Resolves to an [`AsyncLocker`] once every registered mutex has been
+/// acquired, in ascending address order.
+pub struct Finish<'a, R: RawMutexAsync> {
+    locks: SmallVec<[&'a R; 4]>,
+    index: usize,
+    current: Option<R::LockFuture<'a>>,
+}
+
+impl<'a, R: RawMutexAsync> Future for Finish<'a, R> {
+    type Output = AsyncLocker<'a, R>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // safety: `this.current`'s future is owned by `self` and is never
+        // moved out of while it may have been polled, so it upholds the
+        // pinning guarantee for as long as `self` itself stays pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        loop {
+            if this.index == this.locks.len() {
+                let locks = core::mem::take(&mut this.locks);
+                this.index = 0;
+                return Poll::Ready(AsyncLocker { locks });
+            }
+            if this.current.is_none() {
+                this.current = Some(this.locks[this.index].lock());
+            }
+            let fut = unsafe { Pin::new_unchecked(this.current.as_mut().unwrap()) };
+            match fut.poll(cx) {
+                Poll::Ready(()) => {
+                    this.current = None;
+                    this.index += 1;
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<'a, R: RawMutexAsync> Drop for Finish<'a, R> {
+    fn drop(&mut self) {
+        // Only the locks already fully acquired (`0..index`) are actually
+        // held; `current`, if any, is still waiting to acquire `locks[index]`
+        // and is responsible for its own cancellation.
+        for raw in &self.locks[..self.index] {
+            // safety: these were acquired in `poll` above and not yet
+            // transferred into an `AsyncLocker`, since when the future
+            // resolves to `Ready`, `poll` empties `locks` and resets `index`.
+            unsafe {
+                raw.unlock();
+            }
+        }
+    }
+}
+
+/// Guard object representing a set of locked async mutexes.
+///
+/// Created by awaiting the future returned from `AsyncBuilder::finish`.
+#[must_use = "if unused, the Mutexes will immediately unlock"]
+pub struct AsyncLocker<'a, R: RawMutexAsync> {
+    locks: SmallVec<[&'a R; 4]>,
+}
+
+impl<'a, R: RawMutexAsync> Drop for AsyncLocker<'a, R> {
+    fn drop(&mut self) {
+        for raw in &self.locks {
+            // safety: These locks were locked by the `Finish` future when
+            // this `AsyncLocker` was constructed.
+            unsafe {
+                raw.unlock();
+            }
+        }
+    }
+}
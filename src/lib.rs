@@ -9,6 +9,27 @@
 //! method. Locks will be acquired when [`Builder::finish`] is called, in
 //! ascending address order.
 //!
+//! `lock_api::RwLock` values may also be registered, using
+//! [`Builder::add_read`] and [`Builder::add_write`], and will be locked in
+//! shared or exclusive mode respectively alongside the plain mutexes, still
+//! in a single ascending address order.
+//!
+//! [`Builder::finish`] only avoids deadlocks if every caller locking these
+//! mutexes goes through address-ordered acquisition. [`Builder::try_finish`]
+//! and [`Builder::finish_robust`] instead use a lock-all-or-none algorithm,
+//! so they remain deadlock-free even against code elsewhere that locks the
+//! same mutexes in an arbitrary order.
+//!
+//! With the `async` feature enabled, [`AsyncBuilder`] provides the same
+//! address-ordered acquisition for async-aware mutexes, acquiring one lock
+//! at a time rather than requiring a blocking OS primitive.
+//!
+//! With the `std` feature enabled, [`PoisonableMutex`] and
+//! [`Builder::add_poisonable`]/[`Builder::finish_poisonable`] borrow
+//! `std::sync::Mutex`'s poisoning strategy: if a thread panics while
+//! holding a `Locker`, every poisonable mutex it held is flagged poisoned,
+//! and the next `finish_poisonable` call surfaces that.
+//!
 //! # Example
 //!
 //! ```
@@ -39,11 +60,120 @@
 //! ```
 
 use core::marker::PhantomData;
-use lock_api::{Mutex, RawMutex};
+#[cfg(feature = "std")]
+use core::sync::atomic::{AtomicBool, Ordering};
+use lock_api::{Mutex, RawMutex, RawRwLock, RwLock};
 use smallvec::SmallVec;
+#[cfg(feature = "std")]
+extern crate std;
 
 // Invariant marker lifetime helper.
-type Id<'id> = PhantomData<&'id mut &'id u8>;
+pub(crate) type Id<'id> = PhantomData<&'id mut &'id u8>;
+
+#[cfg(feature = "async")]
+mod asynch;
+#[cfg(feature = "async")]
+pub use asynch::{AsyncBuilder, AsyncLocker, AsyncMutex, AsyncToken, Finish, RawMutexAsync};
+
+/// Object-safe facade over [`RawRwLock`], used to type-erase the raw
+/// `RwLock` type of entries registered with [`Builder::add_read`] and
+/// [`Builder::add_write`]. `RawRwLock` itself has an associated constant, so
+/// it cannot be used as a trait object directly.
+trait ErasedRwLock {
+    fn lock_shared(&self);
+    fn try_lock_shared(&self) -> bool;
+    unsafe fn unlock_shared(&self);
+    fn lock_exclusive(&self);
+    fn try_lock_exclusive(&self) -> bool;
+    unsafe fn unlock_exclusive(&self);
+}
+
+impl<R2: RawRwLock> ErasedRwLock for R2 {
+    fn lock_shared(&self) {
+        RawRwLock::lock_shared(self)
+    }
+    fn try_lock_shared(&self) -> bool {
+        RawRwLock::try_lock_shared(self)
+    }
+    unsafe fn unlock_shared(&self) {
+        RawRwLock::unlock_shared(self)
+    }
+    fn lock_exclusive(&self) {
+        RawRwLock::lock_exclusive(self)
+    }
+    fn try_lock_exclusive(&self) -> bool {
+        RawRwLock::try_lock_exclusive(self)
+    }
+    unsafe fn unlock_exclusive(&self) {
+        RawRwLock::unlock_exclusive(self)
+    }
+}
+
+/// A single raw lock registered with a `Builder`, tagged with how it should
+/// be acquired.
+enum LockEntry<'a, R> {
+    Mutex(&'a R),
+    Read(&'a dyn ErasedRwLock),
+    Write(&'a dyn ErasedRwLock),
+}
+
+// Written by hand, rather than derived, so that `R` doesn't need to be
+// `Clone`/`Copy` itself: every variant only ever holds a reference.
+impl<'a, R> Clone for LockEntry<'a, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, R> Copy for LockEntry<'a, R> {}
+
+impl<'a, R: RawMutex> LockEntry<'a, R> {
+    fn addr(&self) -> usize {
+        match *self {
+            LockEntry::Mutex(r) => r as *const R as usize,
+            LockEntry::Read(r) | LockEntry::Write(r) => {
+                (r as *const dyn ErasedRwLock).cast::<()>() as usize
+            }
+        }
+    }
+
+    fn is_write(&self) -> bool {
+        !matches!(self, LockEntry::Read(_))
+    }
+
+    /// Upgrade a `Read` entry to a `Write` entry, leaving other entries
+    /// unchanged.
+    fn to_write(self) -> Self {
+        match self {
+            LockEntry::Read(r) => LockEntry::Write(r),
+            other => other,
+        }
+    }
+
+    fn lock(&self) {
+        match *self {
+            LockEntry::Mutex(r) => r.lock(),
+            LockEntry::Read(r) => r.lock_shared(),
+            LockEntry::Write(r) => r.lock_exclusive(),
+        }
+    }
+
+    fn try_lock(&self) -> bool {
+        match *self {
+            LockEntry::Mutex(r) => r.try_lock(),
+            LockEntry::Read(r) => r.try_lock_shared(),
+            LockEntry::Write(r) => r.try_lock_exclusive(),
+        }
+    }
+
+    unsafe fn unlock(&self) {
+        match *self {
+            LockEntry::Mutex(r) => r.unlock(),
+            LockEntry::Read(r) => r.unlock_shared(),
+            LockEntry::Write(r) => r.unlock_exclusive(),
+        }
+    }
+}
 
 /// Reference a mutex which was registered with a `Locker`.
 ///
@@ -71,13 +201,196 @@ impl<'id, 'a, R: RawMutex, T> Token<'id, 'a, R, T> {
         // lock is currently being held.
         unsafe { &mut *self.mutex.data_ptr() }
     }
+
+    /// Project this token onto a sub-field of the locked data, yielding a
+    /// [`MappedToken`] which can be used in its place.
+    ///
+    /// This mirrors `parking_lot::MappedMutexGuard`, but since access here is
+    /// gated by the `Locker` reference rather than by a guard's lifetime,
+    /// the mapped token only needs to cache the projected pointer. The
+    /// `Locker` argument proves the lock is actually held at the point `f`
+    /// runs, the same way it does for `get`/`get_mut`.
+    pub fn map<U>(
+        self,
+        _locker: &Locker<'id, 'a, R>,
+        f: impl FnOnce(&mut T) -> &mut U,
+    ) -> MappedToken<'id, 'a, R, U> {
+        debug_assert!(self.mutex.is_locked());
+        // safety: The invariant 'id lifetime ensures that this `Token` is
+        // derived from the same `Builder` as the `Locker` argument, meaning
+        // the lock is currently held, so forming `&mut T` here is sound. We
+        // never dereference `ptr` until `get`/`get_mut` are called with a
+        // `Locker` proving the lock is still held.
+        let ptr = unsafe { f(&mut *self.mutex.data_ptr()) as *mut U };
+        MappedToken {
+            mutex: self.mutex,
+            ptr,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Object-safe facade over `is_locked`, used so a [`MappedToken`] can debug-
+/// assert its originating mutex is held without needing to also carry that
+/// mutex's data type.
+trait IsLocked {
+    fn is_locked(&self) -> bool;
 }
 
-/// Builder type used to register `Mutex` references to be locked.
+impl<R: RawMutex, T> IsLocked for Mutex<R, T> {
+    fn is_locked(&self) -> bool {
+        Mutex::is_locked(self)
+    }
+}
+
+/// A [`Token`] that has been projected onto a sub-field of its locked data,
+/// created using [`Token::map`].
+///
+/// When combined with a `Locker`, may be used to access the projected data.
+pub struct MappedToken<'id, 'a, R: RawMutex, U> {
+    mutex: &'a dyn IsLocked,
+    ptr: *mut U,
+    marker: PhantomData<(Id<'id>, &'a mut U, R::GuardMarker)>,
+}
+
+// safety: `MappedToken` only exposes the pointed-to `U` through `get`/
+// `get_mut`, gated on the same `Locker` as a plain `Token`, so it is
+// `Send`/`Sync` under the same conditions a `&mut U` would be.
+unsafe impl<'id, 'a, R: RawMutex, U: Send> Send for MappedToken<'id, 'a, R, U> {}
+unsafe impl<'id, 'a, R: RawMutex, U: Sync> Sync for MappedToken<'id, 'a, R, U> {}
+
+impl<'id, 'a, R: RawMutex, U> MappedToken<'id, 'a, R, U> {
+    /// Get a shared reference to the value locked with this token.
+    pub fn get<'b>(&'b self, _locker: &'b Locker<'id, 'a, R>) -> &'b U {
+        debug_assert!(self.mutex.is_locked());
+        // safety: see `Token::get`.
+        unsafe { &*self.ptr }
+    }
+
+    /// Get a mutable reference to the value locked with this token.
+    pub fn get_mut<'b>(&'b mut self, _locker: &'b Locker<'id, 'a, R>) -> &'b mut U {
+        debug_assert!(self.mutex.is_locked());
+        // safety: see `Token::get_mut`.
+        unsafe { &mut *self.ptr }
+    }
+}
+
+/// Backing storage for a [`TokenVec`], depending on whether its mutexes were
+/// registered via `add_slice` or `add_iter`.
+enum TokenStorage<'a, R: RawMutex, T> {
+    Slice(&'a [Mutex<R, T>]),
+    Scattered(SmallVec<[&'a Mutex<R, T>; 4]>),
+}
+
+impl<'a, R: RawMutex, T> TokenStorage<'a, R, T> {
+    fn len(&self) -> usize {
+        match self {
+            TokenStorage::Slice(s) => s.len(),
+            TokenStorage::Scattered(v) => v.len(),
+        }
+    }
+
+    fn get(&self, index: usize) -> &'a Mutex<R, T> {
+        match self {
+            TokenStorage::Slice(s) => &s[index],
+            TokenStorage::Scattered(v) => v[index],
+        }
+    }
+}
+
+/// An indexable handle to a runtime-sized set of mutexes registered with a
+/// `Locker`, created using [`Builder::add_slice`] or [`Builder::add_iter`].
+///
+/// When combined with a `Locker`, may be used to access the locked data.
+pub struct TokenVec<'id, 'a, R: RawMutex, T> {
+    mutexes: TokenStorage<'a, R, T>,
+    marker: PhantomData<(Id<'id>, &'a mut T, R::GuardMarker)>,
+}
+
+impl<'id, 'a, R: RawMutex, T> TokenVec<'id, 'a, R, T> {
+    /// The number of mutexes registered with this `TokenVec`.
+    pub fn len(&self) -> usize {
+        self.mutexes.len()
+    }
+
+    /// Whether this `TokenVec` has no mutexes registered with it.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get a shared reference to the value locked at `index`.
+    pub fn get<'b>(&'b self, index: usize, _locker: &'b Locker<'id, 'a, R>) -> &'b T {
+        let mutex = self.mutexes.get(index);
+        debug_assert!(mutex.is_locked());
+        // safety: see `Token::get`.
+        unsafe { &*mutex.data_ptr() }
+    }
+
+    /// Get a mutable reference to the value locked at `index`.
+    pub fn get_mut<'b>(&'b mut self, index: usize, _locker: &'b Locker<'id, 'a, R>) -> &'b mut T {
+        let mutex = self.mutexes.get(index);
+        debug_assert!(mutex.is_locked());
+        // safety: see `Token::get_mut`.
+        unsafe { &mut *mutex.data_ptr() }
+    }
+}
+
+/// Reference an `RwLock` which was registered for shared access with a
+/// `Locker`, using [`Builder::add_read`].
+///
+/// When combined with a `Locker`, may be used to read the locked data.
+pub struct ReadToken<'id, 'a, R: RawMutex, R2: RawRwLock, T> {
+    rwlock: &'a RwLock<R2, T>,
+    marker: PhantomData<(Id<'id>, &'a T, R::GuardMarker, R2::GuardMarker)>,
+}
+
+impl<'id, 'a, R: RawMutex, R2: RawRwLock, T> ReadToken<'id, 'a, R, R2, T> {
+    /// Get a shared reference to the value locked with this token.
+    pub fn get<'b>(&'b self, _locker: &'b Locker<'id, 'a, R>) -> &'b T {
+        debug_assert!(self.rwlock.is_locked());
+        // safety: The invariant 'id lifetime ensures that this `ReadToken` is
+        // derived from the same `Builder` as the `Locker` argument, meaning
+        // the lock is currently held, at least for shared access.
+        unsafe { &*self.rwlock.data_ptr() }
+    }
+}
+
+/// Reference an `RwLock` which was registered for exclusive access with a
+/// `Locker`, using [`Builder::add_write`].
+///
+/// When combined with a `Locker`, may be used to read or write the locked
+/// data.
+pub struct WriteToken<'id, 'a, R: RawMutex, R2: RawRwLock, T> {
+    rwlock: &'a RwLock<R2, T>,
+    marker: PhantomData<(Id<'id>, &'a mut T, R::GuardMarker, R2::GuardMarker)>,
+}
+
+impl<'id, 'a, R: RawMutex, R2: RawRwLock, T> WriteToken<'id, 'a, R, R2, T> {
+    /// Get a shared reference to the value locked with this token.
+    pub fn get<'b>(&'b self, _locker: &'b Locker<'id, 'a, R>) -> &'b T {
+        debug_assert!(self.rwlock.is_locked());
+        // safety: see `ReadToken::get`. This token additionally guarantees
+        // the lock is held exclusively.
+        unsafe { &*self.rwlock.data_ptr() }
+    }
+
+    /// Get a mutable reference to the value locked with this token.
+    pub fn get_mut<'b>(&'b mut self, _locker: &'b Locker<'id, 'a, R>) -> &'b mut T {
+        debug_assert!(self.rwlock.is_locked());
+        // safety: see `ReadToken::get`. This token additionally guarantees
+        // the lock is held exclusively.
+        unsafe { &mut *self.rwlock.data_ptr() }
+    }
+}
+
+/// Builder type used to register `Mutex` and `RwLock` references to be
+/// locked.
 ///
 /// Created using the `multilock` method.
 pub struct Builder<'id, 'a, R: RawMutex> {
-    locks: SmallVec<[&'a R; 4]>,
+    locks: SmallVec<[LockEntry<'a, R>; 4]>,
+    #[cfg(feature = "std")]
+    poison_flags: SmallVec<[&'a AtomicBool; 4]>,
     marker: PhantomData<(Id<'id>, R::GuardMarker)>,
 }
 
@@ -87,7 +400,7 @@ impl<'id, 'a, R: RawMutex> Builder<'id, 'a, R> {
         // Safety: Acquiring a reference to lock and unlock the underlying raw
         // mutex in other methods.
         unsafe {
-            self.locks.push(mutex.raw());
+            self.locks.push(LockEntry::Mutex(mutex.raw()));
         }
         Token {
             mutex,
@@ -95,45 +408,369 @@ impl<'id, 'a, R: RawMutex> Builder<'id, 'a, R> {
         }
     }
 
-    /// Lock all mutexes registered with this builder, producing a `Locker` which
-    /// will allow access to the locked data.
+    /// Register every mutex in a slice to be locked by this `Builder`,
+    /// returning an indexable [`TokenVec`] in place of one `Token` per
+    /// mutex.
+    ///
+    /// Useful when the number of mutexes to lock together (e.g. the nodes
+    /// of a graph) is only known at runtime.
+    pub fn add_slice<T>(&mut self, mutexes: &'a [Mutex<R, T>]) -> TokenVec<'id, 'a, R, T> {
+        for mutex in mutexes {
+            // Safety: Acquiring a reference to lock and unlock the
+            // underlying raw mutex in other methods.
+            unsafe {
+                self.locks.push(LockEntry::Mutex(mutex.raw()));
+            }
+        }
+        TokenVec {
+            mutexes: TokenStorage::Slice(mutexes),
+            marker: PhantomData,
+        }
+    }
+
+    /// Register every mutex yielded by an iterator to be locked by this
+    /// `Builder`, returning an indexable [`TokenVec`] in place of one
+    /// `Token` per mutex.
+    ///
+    /// Like [`Builder::add_slice`], but for mutexes that aren't stored
+    /// contiguously, e.g. behind separate allocations.
+    pub fn add_iter<T>(
+        &mut self,
+        mutexes: impl IntoIterator<Item = &'a Mutex<R, T>>,
+    ) -> TokenVec<'id, 'a, R, T> {
+        let mutexes: SmallVec<[&'a Mutex<R, T>; 4]> = mutexes.into_iter().collect();
+        for mutex in &mutexes {
+            // Safety: Acquiring a reference to lock and unlock the
+            // underlying raw mutex in other methods.
+            unsafe {
+                self.locks.push(LockEntry::Mutex(mutex.raw()));
+            }
+        }
+        TokenVec {
+            mutexes: TokenStorage::Scattered(mutexes),
+            marker: PhantomData,
+        }
+    }
+
+    /// Register a new `RwLock` to be locked for shared access by this
+    /// `Builder`.
+    pub fn add_read<R2: RawRwLock, T>(
+        &mut self,
+        rwlock: &'a RwLock<R2, T>,
+    ) -> ReadToken<'id, 'a, R, R2, T> {
+        // Safety: Acquiring a reference to lock and unlock the underlying raw
+        // rwlock in other methods.
+        unsafe {
+            self.locks.push(LockEntry::Read(rwlock.raw()));
+        }
+        ReadToken {
+            rwlock,
+            marker: PhantomData,
+        }
+    }
+
+    /// Register a new `RwLock` to be locked for exclusive access by this
+    /// `Builder`.
+    pub fn add_write<R2: RawRwLock, T>(
+        &mut self,
+        rwlock: &'a RwLock<R2, T>,
+    ) -> WriteToken<'id, 'a, R, R2, T> {
+        // Safety: Acquiring a reference to lock and unlock the underlying raw
+        // rwlock in other methods.
+        unsafe {
+            self.locks.push(LockEntry::Write(rwlock.raw()));
+        }
+        WriteToken {
+            rwlock,
+            marker: PhantomData,
+        }
+    }
+
+    /// Register a new mutex to be locked by this `Builder`, tracking whether
+    /// it was left poisoned by a panic the next time it's acquired with
+    /// [`Builder::finish_poisonable`].
+    #[cfg(feature = "std")]
+    pub fn add_poisonable<T>(&mut self, mutex: &'a PoisonableMutex<R, T>) -> Token<'id, 'a, R, T> {
+        // Safety: Acquiring a reference to lock and unlock the underlying raw
+        // mutex in other methods.
+        unsafe {
+            self.locks.push(LockEntry::Mutex(mutex.mutex.raw()));
+        }
+        self.poison_flags.push(&mutex.poisoned);
+        Token {
+            mutex: &mutex.mutex,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sort a set of registered locks into ascending address order, and
+    /// collapse any duplicate registrations of the same `RwLock` into a
+    /// single acquisition, which is exclusive if any of those registrations
+    /// was made with `add_write` and shared otherwise.
+    ///
+    /// The same `RwLock` may have been registered both for shared access
+    /// (via `add_read`) and exclusive access (via `add_write`). Such
+    /// adjacent duplicates are collapsed, as locking the raw lock for both
+    /// shared and exclusive access from the same thread would self-deadlock.
+    ///
+    /// Registering the same plain `Mutex` more than once is a different
+    /// situation: each registration hands out its own `Token`, so merging
+    /// them into a single acquisition would let two live `Token`s produce
+    /// aliasing `&mut T`s into the same data. That's a logic error in the
+    /// caller, not something this helper can paper over, so it panics
+    /// instead.
+    ///
+    /// # Panics
+    /// Panics if the same `Mutex` address was registered more than once.
+    fn sorted_locks(mut locks: SmallVec<[LockEntry<'a, R>; 4]>) -> SmallVec<[LockEntry<'a, R>; 4]> {
+        locks.sort_unstable_by_key(LockEntry::addr);
+        locks.dedup_by(|later, retained| {
+            if later.addr() != retained.addr() {
+                return false;
+            }
+            assert!(
+                matches!(later, LockEntry::Read(_) | LockEntry::Write(_))
+                    || matches!(retained, LockEntry::Read(_) | LockEntry::Write(_)),
+                "the same `Mutex` was registered with this `Builder` more than once"
+            );
+            if later.is_write() {
+                *retained = retained.to_write();
+            }
+            true
+        });
+        locks
+    }
+
+    /// Try to lock every entry in `locks`, in order, backing out anything
+    /// already acquired as soon as one fails. Returns the index of the lock
+    /// that could not be acquired, if any.
+    fn try_lock_all(locks: &[LockEntry<'a, R>]) -> Result<(), usize> {
+        for (i, lock) in locks.iter().enumerate() {
+            if !lock.try_lock() {
+                for acquired in &locks[..i] {
+                    // safety: these locks were just acquired by the loop
+                    // above.
+                    unsafe {
+                        acquired.unlock();
+                    }
+                }
+                return Err(i);
+            }
+        }
+        Ok(())
+    }
+
+    /// Lock all mutexes and rwlocks registered with this builder, producing
+    /// a `Locker` which will allow access to the locked data.
     pub fn finish(self) -> Locker<'id, 'a, R> {
         // Acquire each lock in our internal `Vec` in address order, which
         // should avoid deadlock issues if sets of mutexes are always locked
         // with this helper type.
-        let mut locks = self.locks;
-        locks.sort_unstable_by_key(|m| *m as *const R);
-        for raw in &locks {
-            raw.lock();
+        #[cfg(feature = "std")]
+        let poison_flags = self.poison_flags;
+        let locks = Self::sorted_locks(self.locks);
+        for lock in &locks {
+            lock.lock();
         }
-        Locker {
-            locks,
-            marker: PhantomData,
+        #[cfg(feature = "std")]
+        return Locker::with_poison_flags(locks, poison_flags);
+        #[cfg(not(feature = "std"))]
+        Locker::new(locks)
+    }
+
+    /// Try to lock all mutexes and rwlocks registered with this builder,
+    /// without blocking.
+    ///
+    /// If any lock is not immediately available, every lock already
+    /// acquired is released and `None` is returned.
+    pub fn try_finish(self) -> Option<Locker<'id, 'a, R>> {
+        #[cfg(feature = "std")]
+        let poison_flags = self.poison_flags;
+        let locks = Self::sorted_locks(self.locks);
+        match Self::try_lock_all(&locks) {
+            #[cfg(feature = "std")]
+            Ok(()) => Some(Locker::with_poison_flags(locks, poison_flags)),
+            #[cfg(not(feature = "std"))]
+            Ok(()) => Some(Locker::new(locks)),
+            Err(_) => None,
+        }
+    }
+
+    /// Lock all mutexes and rwlocks registered with this builder, using a
+    /// lock-all-or-none algorithm that is deadlock-free even if other code
+    /// elsewhere locks the same mutexes in a different order.
+    ///
+    /// Unlike `finish`, this does not rely on every caller acquiring these
+    /// locks through `multilock`'s address ordering: if acquiring a lock
+    /// would block, every lock already taken in this attempt is released
+    /// before retrying, so this thread can never be left holding a prefix of
+    /// the locks while waiting on one some other thread holds.
+    pub fn finish_robust(self) -> Locker<'id, 'a, R> {
+        #[cfg(feature = "std")]
+        let poison_flags = self.poison_flags;
+        let locks = Self::sorted_locks(self.locks);
+        loop {
+            match Self::try_lock_all(&locks) {
+                Ok(()) => break,
+                Err(failed) => {
+                    // Block until the contended lock becomes available, then
+                    // release it and retry the whole acquisition from the
+                    // start. Blocking on a specific lock, rather than
+                    // spinning on `try_lock` alone, guarantees this thread
+                    // eventually makes progress even if other threads keep
+                    // racing it for the same locks in a different order.
+                    locks[failed].lock();
+                    // safety: just acquired above.
+                    unsafe {
+                        locks[failed].unlock();
+                    }
+                    core::hint::spin_loop();
+                }
+            }
+        }
+        #[cfg(feature = "std")]
+        return Locker::with_poison_flags(locks, poison_flags);
+        #[cfg(not(feature = "std"))]
+        Locker::new(locks)
+    }
+
+    /// Lock all mutexes registered with this builder, detecting mutexes
+    /// registered via [`Builder::add_poisonable`] that are flagged poisoned
+    /// because a thread panicked while holding a `Locker` over them. The flag
+    /// stays set until [`PoisonableMutex::clear_poison`] is called.
+    ///
+    /// Like `std::sync::Mutex::lock`, poisoning here is advisory: the
+    /// `Locker` is still returned, wrapped in `Err`, so callers may choose
+    /// to proceed despite the poisoning.
+    #[cfg(feature = "std")]
+    // `PoisonedSet` is large because it carries the `Locker` through so
+    // callers can recover it, the same tradeoff `std::sync::PoisonError`
+    // makes for its own (smaller) guard types.
+    #[allow(clippy::result_large_err)]
+    pub fn finish_poisonable(self) -> Result<Locker<'id, 'a, R>, PoisonedSet<'id, 'a, R>> {
+        let poison_flags = self.poison_flags;
+        let locks = Self::sorted_locks(self.locks);
+        for lock in &locks {
+            lock.lock();
+        }
+        let was_poisoned = poison_flags.iter().any(|flag| flag.load(Ordering::Acquire));
+        let locker = Locker::with_poison_flags(locks, poison_flags);
+        if was_poisoned {
+            Err(PoisonedSet { locker })
+        } else {
+            Ok(locker)
         }
     }
 }
 
-/// Guard object representing a set of locked mutexes.
+/// Guard object representing a set of locked mutexes and rwlocks.
 ///
 /// Created using the `Builder::finish` method.
 #[must_use = "if unused, the Mutexes will immediately unlock"]
 pub struct Locker<'id, 'a, R: RawMutex> {
-    locks: SmallVec<[&'a R; 4]>,
+    locks: SmallVec<[LockEntry<'a, R>; 4]>,
+    #[cfg(feature = "std")]
+    poison_flags: SmallVec<[&'a AtomicBool; 4]>,
     marker: PhantomData<(Id<'id>, R::GuardMarker)>,
 }
 
+impl<'id, 'a, R: RawMutex> Locker<'id, 'a, R> {
+    #[cfg(not(feature = "std"))]
+    fn new(locks: SmallVec<[LockEntry<'a, R>; 4]>) -> Self {
+        Locker {
+            locks,
+            marker: PhantomData,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn with_poison_flags(
+        locks: SmallVec<[LockEntry<'a, R>; 4]>,
+        poison_flags: SmallVec<[&'a AtomicBool; 4]>,
+    ) -> Self {
+        Locker {
+            locks,
+            poison_flags,
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<'id, 'a, R: RawMutex> Drop for Locker<'id, 'a, R> {
     fn drop(&mut self) {
-        for raw in &self.locks {
-            // safety: These locks were locked by `LockBuilder::finish()` when
-            // this `Locker` was constructed.
+        // If we're unwinding from a panic while still holding these locks,
+        // flag every lock registered via `Builder::add_poisonable` as
+        // poisoned, mirroring `std::sync::Mutex`'s own poisoning strategy.
+        #[cfg(feature = "std")]
+        if std::thread::panicking() {
+            for flag in &self.poison_flags {
+                flag.store(true, Ordering::Release);
+            }
+        }
+        for lock in &self.locks {
+            // safety: These locks were locked by the `Builder::finish*`
+            // method that constructed this `Locker`.
             unsafe {
-                raw.unlock();
+                lock.unlock();
             }
         }
     }
 }
 
+/// A mutex that tracks whether it was poisoned by a panic while locked
+/// through [`Builder::add_poisonable`]/[`Builder::finish_poisonable`].
+///
+/// Poisoning is best-effort and only observed for mutexes registered this
+/// way: it has no effect on `Mutex`es registered with plain `add`.
+#[cfg(feature = "std")]
+pub struct PoisonableMutex<R: RawMutex, T> {
+    mutex: Mutex<R, T>,
+    poisoned: AtomicBool,
+}
+
+#[cfg(feature = "std")]
+impl<R: RawMutex, T> PoisonableMutex<R, T> {
+    /// Create a new, unpoisoned `PoisonableMutex` wrapping `val`.
+    pub const fn new(val: T) -> Self {
+        PoisonableMutex {
+            mutex: Mutex::new(val),
+            poisoned: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether this mutex is flagged poisoned, i.e. a thread panicked while
+    /// holding a `Locker` over it (from any of the `Builder::finish*`
+    /// methods) since the flag was last cleared with `clear_poison`.
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Acquire)
+    }
+
+    /// Clear this mutex's poisoned flag, mirroring
+    /// `std::sync::Mutex::clear_poison`.
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Release);
+    }
+}
+
+/// Returned by [`Builder::finish_poisonable`] in place of a `Locker` when one
+/// or more registered mutexes were left poisoned by a panic.
+///
+/// Mirrors `std::sync::PoisonError`: the `Locker` is recoverable via
+/// [`PoisonedSet::into_inner`], so callers may choose to proceed despite the
+/// poisoning.
+#[cfg(feature = "std")]
+pub struct PoisonedSet<'id, 'a, R: RawMutex> {
+    locker: Locker<'id, 'a, R>,
+}
+
+#[cfg(feature = "std")]
+impl<'id, 'a, R: RawMutex> PoisonedSet<'id, 'a, R> {
+    /// Recover the `Locker` despite the poisoning it reported.
+    pub fn into_inner(self) -> Locker<'id, 'a, R> {
+        self.locker
+    }
+}
+
 /// Lock and acquire references to multiple objects without deadlocks.
 ///
 /// See the module-level documentation for details.
@@ -143,6 +780,8 @@ where
 {
     func(Builder {
         locks: SmallVec::new(),
+        #[cfg(feature = "std")]
+        poison_flags: SmallVec::new(),
         marker: PhantomData,
     })
 }
@@ -0,0 +1,92 @@
+#![cfg(feature = "async")]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use multilock::{AsyncBuilder, AsyncMutex, RawMutexAsync};
+
+/// Trivial spinning async raw mutex, used only to exercise the `async`
+/// acquisition path without pulling in an async runtime.
+struct SpinMutex(AtomicBool);
+
+impl RawMutexAsync for SpinMutex {
+    const INIT: Self = SpinMutex(AtomicBool::new(false));
+
+    type LockFuture<'a> = SpinLock<'a>;
+
+    fn lock(&self) -> SpinLock<'_> {
+        SpinLock(self)
+    }
+
+    unsafe fn unlock(&self) {
+        self.0.store(false, Ordering::Release);
+    }
+
+    fn is_locked(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+struct SpinLock<'a>(&'a SpinMutex);
+
+impl<'a> Future for SpinLock<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 .0.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            Poll::Ready(())
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+fn noop_waker() -> Waker {
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(core::ptr::null(), &VTABLE)
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+    unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
+}
+
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = Box::pin(fut);
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(v) => return v,
+            Poll::Pending => {}
+        }
+    }
+}
+
+#[test]
+fn test_multilock_async() {
+    let m1 = AsyncMutex::<SpinMutex, _>::new(5);
+    let m2 = AsyncMutex::<SpinMutex, _>::new("cheese");
+
+    let mut builder = AsyncBuilder::new();
+    let mut m1_token = builder.add(&m1);
+    let mut m2_token = builder.add(&m2);
+
+    block_on(async move {
+        let locker = builder.finish().await;
+
+        // safety: `locker` holds both `m1` and `m2`, since it was produced by
+        // awaiting `builder.finish()` with both tokens registered above.
+        unsafe {
+            assert_eq!(*m1_token.get(&locker), 5);
+            assert_eq!(*m2_token.get(&locker), "cheese");
+
+            *m1_token.get_mut(&locker) = 10;
+            *m2_token.get_mut(&locker) = "pies";
+        }
+    });
+}
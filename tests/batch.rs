@@ -0,0 +1,44 @@
+use multilock::multilock;
+use parking_lot::Mutex;
+
+#[test]
+fn test_add_slice() {
+    let mutexes = [Mutex::new(1), Mutex::new(2), Mutex::new(3)];
+
+    multilock(|mut builder| {
+        let mut tokens = builder.add_slice(&mutexes);
+        assert_eq!(tokens.len(), 3);
+
+        let locker = builder.finish();
+
+        for i in 0..tokens.len() {
+            assert_eq!(*tokens.get(i, &locker), i as i32 + 1);
+            *tokens.get_mut(i, &locker) += 10;
+        }
+    });
+
+    assert_eq!(mutexes[0].lock().clone(), 11);
+    assert_eq!(mutexes[1].lock().clone(), 12);
+    assert_eq!(mutexes[2].lock().clone(), 13);
+}
+
+#[test]
+fn test_add_iter() {
+    let m1 = Mutex::new("a");
+    let m2 = Mutex::new("b");
+    let mutexes = [&m1, &m2];
+
+    multilock(|mut builder| {
+        let mut tokens = builder.add_iter(mutexes.iter().copied());
+        assert_eq!(tokens.len(), 2);
+
+        let locker = builder.finish();
+
+        assert_eq!(*tokens.get(0, &locker), "a");
+        assert_eq!(*tokens.get(1, &locker), "b");
+        *tokens.get_mut(0, &locker) = "c";
+    });
+
+    assert_eq!(*m1.lock(), "c");
+    assert_eq!(*m2.lock(), "b");
+}
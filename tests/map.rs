@@ -0,0 +1,26 @@
+use multilock::multilock;
+use parking_lot::Mutex;
+
+struct Pair {
+    a: i32,
+    b: &'static str,
+}
+
+#[test]
+fn test_mapped_token() {
+    let m = Mutex::new(Pair { a: 5, b: "cheese" });
+
+    multilock(|mut builder| {
+        let token = builder.add(&m);
+
+        let locker = builder.finish();
+
+        let mut a_token = token.map(&locker, |pair| &mut pair.a);
+
+        assert_eq!(*a_token.get(&locker), 5);
+        *a_token.get_mut(&locker) = 10;
+    });
+
+    assert_eq!(m.lock().a, 10);
+    assert_eq!(m.lock().b, "cheese");
+}
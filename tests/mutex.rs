@@ -35,3 +35,19 @@ fn test_mutex() {
     assert_eq!(m1.into_inner(), 10);
     assert_eq!(m2.into_inner(), "pies");
 }
+
+#[test]
+#[should_panic(expected = "registered with this `Builder` more than once")]
+fn test_duplicate_mutex_registration_panics() {
+    let m = Mutex::new(5);
+
+    multilock(|mut builder| {
+        // Registering the same `Mutex` twice must not collapse into a single
+        // acquisition: that would let the two `Token`s returned below hand
+        // out aliasing `&mut` references to the same data.
+        builder.add(&m);
+        builder.add(&m);
+
+        let _locker = builder.finish();
+    });
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "std")]
+
+use multilock::{multilock, PoisonableMutex};
+use parking_lot::RawMutex;
+use std::panic;
+
+#[test]
+fn test_finish_poisonable_detects_panic() {
+    let m1 = PoisonableMutex::<RawMutex, _>::new(5);
+    let m2 = PoisonableMutex::<RawMutex, _>::new("cheese");
+
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        multilock(|mut builder| {
+            builder.add_poisonable(&m1);
+            builder.add_poisonable(&m2);
+
+            let _locker = match builder.finish_poisonable() {
+                Ok(locker) => locker,
+                Err(_) => panic!("locks should not be poisoned yet"),
+            };
+            panic!("oh no");
+        })
+    }));
+    assert!(result.is_err());
+
+    assert!(m1.is_poisoned());
+    assert!(m2.is_poisoned());
+
+    multilock(|mut builder| {
+        let token = builder.add_poisonable(&m1);
+        builder.add_poisonable(&m2);
+
+        let locker = match builder.finish_poisonable() {
+            Ok(_) => panic!("locks should be reported poisoned"),
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        assert_eq!(*token.get(&locker), 5);
+    });
+
+    m1.clear_poison();
+    m2.clear_poison();
+    assert!(!m1.is_poisoned());
+    assert!(!m2.is_poisoned());
+}
+
+#[test]
+fn test_finish_detects_panic_for_poisonable_mutex() {
+    // Poisoning must be tracked regardless of which `finish*` method was
+    // used to acquire the lock: a mutex registered with `add_poisonable`
+    // is just as poisoned by a panic whether it was locked with
+    // `finish_poisonable`, `finish`, `try_finish`, or `finish_robust`.
+    let m = PoisonableMutex::<RawMutex, _>::new(5);
+
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        multilock(|mut builder| {
+            builder.add_poisonable(&m);
+            let _locker = builder.finish();
+            panic!("oh no");
+        })
+    }));
+    assert!(result.is_err());
+
+    assert!(m.is_poisoned());
+}
@@ -0,0 +1,57 @@
+use multilock::multilock;
+use parking_lot::{RawMutex, RwLock};
+
+#[test]
+fn test_rwlock() {
+    let m1 = RwLock::new(5);
+    let m2 = RwLock::new("cheese");
+
+    // No plain mutexes are registered in this test, so the mutex raw type
+    // can't be inferred from a call to `add` and must be spelled out.
+    multilock::<RawMutex, _, _>(|mut builder| {
+        let m1_token = builder.add_read(&m1);
+        let mut m2_token = builder.add_write(&m2);
+
+        assert!(!m1.is_locked());
+        assert!(!m2.is_locked());
+
+        let locker = builder.finish();
+
+        assert!(m1.is_locked());
+        assert!(m2.is_locked_exclusive());
+        assert_eq!(*m1_token.get(&locker), 5);
+        assert_eq!(*m2_token.get(&locker), "cheese");
+
+        *m2_token.get_mut(&locker) = "pies";
+
+        drop(locker);
+
+        assert!(!m1.is_locked());
+        assert!(!m2.is_locked());
+    });
+
+    assert_eq!(*m1.read(), 5);
+    assert_eq!(*m2.read(), "pies");
+}
+
+#[test]
+fn test_rwlock_read_write_same_lock_collapses() {
+    let m = RwLock::new(5);
+
+    multilock::<RawMutex, _, _>(|mut builder| {
+        let read_token = builder.add_read(&m);
+        let mut write_token = builder.add_write(&m);
+
+        let locker = builder.finish();
+
+        assert!(m.is_locked_exclusive());
+        assert_eq!(*read_token.get(&locker), 5);
+        *write_token.get_mut(&locker) = 10;
+
+        drop(locker);
+
+        assert!(!m.is_locked());
+    });
+
+    assert_eq!(*m.read(), 10);
+}
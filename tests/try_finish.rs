@@ -0,0 +1,82 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use multilock::multilock;
+use parking_lot::Mutex;
+
+#[test]
+fn test_try_finish_succeeds_when_unlocked() {
+    let m1 = Mutex::new(5);
+    let m2 = Mutex::new("cheese");
+
+    multilock(|mut builder| {
+        let m1_token = builder.add(&m1);
+        let m2_token = builder.add(&m2);
+
+        let locker = builder.try_finish().expect("locks were free");
+
+        assert_eq!(*m1_token.get(&locker), 5);
+        assert_eq!(*m2_token.get(&locker), "cheese");
+    });
+
+    assert!(!m1.is_locked());
+    assert!(!m2.is_locked());
+}
+
+#[test]
+fn test_try_finish_fails_and_releases_on_contention() {
+    let m1 = Mutex::new(5);
+    let m2 = Mutex::new("cheese");
+
+    // Hold `m2` so the builder can't acquire it.
+    let m2_guard = m2.lock();
+
+    multilock(|mut builder| {
+        builder.add(&m1);
+        builder.add(&m2);
+
+        assert!(builder.try_finish().is_none());
+    });
+
+    // `m1` must have been released again after the failed attempt.
+    assert!(!m1.is_locked());
+    drop(m2_guard);
+}
+
+#[test]
+fn test_finish_robust_waits_out_contention() {
+    let m1 = Mutex::new(5);
+    let m2 = Mutex::new("cheese");
+    let released = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            let _guard = m1.lock();
+            thread::sleep(Duration::from_millis(50));
+            released.store(true, Ordering::Release);
+        });
+
+        // Give the background thread a chance to actually grab `m1` before
+        // `finish_robust` starts contending for it.
+        thread::sleep(Duration::from_millis(10));
+
+        multilock(|mut builder| {
+            let m1_token = builder.add(&m1);
+            let m2_token = builder.add(&m2);
+
+            let locker = builder.finish_robust();
+
+            // `finish_robust` can only return once every lock in this call is
+            // actually held, so `m1` being held must mean the background
+            // thread already released it.
+            assert!(released.load(Ordering::Acquire));
+
+            assert_eq!(*m1_token.get(&locker), 5);
+            assert_eq!(*m2_token.get(&locker), "cheese");
+        });
+    });
+
+    assert!(!m1.is_locked());
+    assert!(!m2.is_locked());
+}